@@ -1,14 +1,80 @@
-use serde_json::{self, Value};
+use serde_json::{self, Value, Map};
 use std::path::Path;
 use std::fs;
+use std::io::{self, Read};
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use glob::glob;
 use regex::{Regex, Captures};
 
 #[derive(Parser, Debug)]
 #[command(author, version)]
 struct Args {
+  #[command(subcommand)]
+  command: Command
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+  /// Replace `'key' | translate` occurrences in search_path with values from path
+  Replace(ReplaceArgs),
+  /// Scaffold a translation file from the keys used under search_path
+  Extract(ExtractArgs),
+  /// Report keys with no resolvable translation, exiting non-zero if any are found
+  Check(CheckArgs)
+}
+
+#[derive(Parser, Debug)]
+struct ReplaceArgs {
+  #[arg(short, long)]
+  path: String,
+
+  /// Required unless --stdin is set
+  #[arg(short, long, required_unless_present = "stdin")]
+  search_path: Option<String>,
+
+  /// Required unless --stdin is set
+  #[arg(short, long, required_unless_present = "stdin")]
+  ignore: Option<String>,
+
+  #[arg(long)]
+  dry_run: bool,
+
+  /// Read a single document from stdin and write the translated result to
+  /// stdout instead of globbing search_path and writing files in place
+  #[arg(long)]
+  stdin: bool,
+
+  /// What to substitute when a key has no match
+  #[arg(long, value_enum, default_value_t = MissingMode::Empty)]
+  missing: MissingMode,
+
+  /// Translation globs consulted in order when --missing=fallback
+  #[arg(long)]
+  fallback: Vec<String>,
+
+  /// Filter keyword to match in place of `translate`, e.g. `'key' | i18n`
+  #[arg(long, default_value = "translate")]
+  filter_name: String
+}
+
+#[derive(ValueEnum, Clone, Debug, Default)]
+enum MissingMode {
+  /// Substitute an empty string (the default). A sample in which no key
+  /// resolves counts as having no replacement and is left unchanged
+  #[default]
+  Empty,
+  /// Substitute the bare key
+  Key,
+  /// Leave the whole `{{ 'key' | translate }}` match untouched
+  Original,
+  /// Consult --fallback globs, in order, for the first string match
+  Fallback
+}
+
+#[derive(Parser, Debug)]
+struct ExtractArgs {
+  /// Translation file to write (merged with if it already exists)
   #[arg(short, long)]
   path: String,
 
@@ -17,26 +83,149 @@ struct Args {
 
   #[arg(short, long)]
   ignore: String,
-  
+
   #[arg(long)]
-  dry_run: bool
+  dry_run: bool,
+
+  /// Filter keyword to match in place of `translate`, e.g. `'key' | i18n`
+  #[arg(long, default_value = "translate")]
+  filter_name: String
+}
+
+#[derive(Parser, Debug)]
+struct CheckArgs {
+  #[arg(short, long)]
+  path: String,
+
+  #[arg(short, long)]
+  search_path: String,
+
+  #[arg(short, long)]
+  ignore: String,
+
+  /// Filter keyword to match in place of `translate`, e.g. `'key' | i18n`
+  #[arg(long, default_value = "translate")]
+  filter_name: String
 }
 
 fn main() {
   let args = Args::parse();
 
-  apply_translation(load_translations(&args.path), &args.ignore, &args.search_path, args.dry_run);
+  match args.command {
+    Command::Replace(replace_args) => {
+      let fallback_maps: Vec<Value> = replace_args.fallback.iter()
+        .flat_map(|glob_pattern| load_translations(glob_pattern, &replace_args.filter_name))
+        .map(|finder| finder.map)
+        .collect();
+
+      let translations: Vec<TranslateFinder> = load_translations(&replace_args.path, &replace_args.filter_name)
+        .into_iter()
+        .map(|finder| finder.with_missing_mode(replace_args.missing.clone(), fallback_maps.clone()))
+        .collect();
+
+      if replace_args.stdin {
+        apply_translation_stdin(&translations);
+      } else {
+        let search_path = replace_args.search_path.expect("clap guarantees this is set when --stdin is absent");
+        let ignore = replace_args.ignore.expect("clap guarantees this is set when --stdin is absent");
+        apply_translation(translations, &ignore, &search_path, replace_args.dry_run);
+      }
+    }
+    Command::Extract(extract_args) => {
+      extract_translations(&extract_args.path, &extract_args.ignore, &extract_args.search_path, extract_args.dry_run, &extract_args.filter_name);
+    }
+    Command::Check(check_args) => {
+      let has_unresolved = check_translations(&check_args.path, &check_args.ignore, &check_args.search_path, &check_args.filter_name);
+      if has_unresolved {
+        std::process::exit(1);
+      }
+    }
+  }
+}
+
+// Walks search_path and reports every translate key that none of the loaded
+// translation maps resolve to a string, grouped by file with line numbers.
+// Returns true if any unresolved key was found, so the caller can exit non-zero.
+fn check_translations(path: &str, ignore: &str, search_path: &str, filter_name: &str) -> bool {
+  let translations = load_translations(path, filter_name);
+  let ignore_regex = Regex::new(ignore).unwrap();
+  let key_regex = build_translate_regex(filter_name);
+
+  let mut has_unresolved = false;
+
+  for p in glob(search_path).expect("Failed to read glob") {
+    match p {
+      Ok(entry) => {
+        if !ignore_regex.is_match(&entry.as_os_str().to_str().unwrap_or("")) {
+          let file_as_string = fs::read_to_string(&entry).unwrap();
+          let mut file_header_printed = false;
+
+          for (line_number, line) in file_as_string.lines().enumerate() {
+            for caps in key_regex.captures_iter(line) {
+              let key = caps["key"].trim();
+              let resolved = translations.iter()
+                .any(|finder| read_json_path(&finder.map, key).map_or(false, |v| v.is_string()));
+
+              if !resolved {
+                if !file_header_printed {
+                  println!("{:?}:", &entry);
+                  file_header_printed = true;
+                }
+                println!("  line {}: unresolved key {:?}", line_number + 1, key);
+                has_unresolved = true;
+              }
+            }
+          }
+        }
+      }
+      _ => {}
+    };
+  }
+
+  has_unresolved
 }
 
-fn load_translations(translations_glob: &str) -> Vec<TranslateFinder> {
+fn extract_translations(path: &str, ignore: &str, search_path: &str, dry_run: bool, filter_name: &str) {
+  let ignore_regex = Regex::new(ignore).unwrap();
+  let key_regex = build_translate_regex(filter_name);
+
+  let mut skeleton = get_json_value_from_fs_path(Path::new(path)).unwrap_or(Value::Object(Map::new()));
+
+  for p in glob(search_path).expect("Failed to read glob") {
+    match p {
+      Ok(entry) => {
+        if !ignore_regex.is_match(&entry.as_os_str().to_str().unwrap_or("")) {
+          let file_as_string = fs::read_to_string(&entry).unwrap();
+          for caps in key_regex.captures_iter(&file_as_string) {
+            let key = caps["key"].trim();
+            if read_json_path(&skeleton, key).is_none() {
+              set_json_path(&mut skeleton, key, Value::String(String::from(key)));
+            }
+          }
+        }
+      }
+      _ => {}
+    };
+  }
+
+  let serialized = serde_json::to_string_pretty(&skeleton).unwrap();
+  if dry_run {
+    println!("{}", serialized);
+  } else {
+    fs::write(path, serialized).unwrap();
+  }
+}
+
+fn load_translations(translations_glob: &str, filter_name: &str) -> Vec<TranslateFinder> {
   glob(translations_glob)
     .expect(&format!("Panicked while reading glob: {}", translations_glob))
     .filter(|p| p.is_ok())
     .map(|entry| {
       let entry = entry.unwrap();
-      TranslateFinder::new(
+      TranslateFinder::with_filter_name(
         get_json_value_from_fs_path(&Path::new(&entry))
-        .expect(&format!("Error parsing json at {:?}", &entry))
+        .expect(&format!("Error parsing json at {:?}", &entry)),
+        filter_name
       )
     })
     .collect()
@@ -70,40 +259,129 @@ fn apply_translation(translations: Vec<TranslateFinder>, ignore: &String, search
   }
 }
 
+// Reads a single document from stdin and writes the translated result to
+// stdout, using the same match-then-replace pass as `apply_translation` but
+// without touching the filesystem, so the tool can sit in a shell pipeline.
+fn apply_translation_stdin(translations: &[TranslateFinder]) {
+  let mut input = String::new();
+  io::stdin().read_to_string(&mut input).expect("Failed to read stdin");
+
+  print!("{}", translate_document(translations, &input));
+}
+
+// Runs the same match-then-replace pass as `apply_translation_stdin` over an
+// in-memory string, so it can be exercised without going through stdin.
+fn translate_document(translations: &[TranslateFinder], input: &str) -> String {
+  let mut output = input.to_string();
+  for translate_finder in translations.iter() {
+    if translate_finder.is_match(input) {
+      if let Some(replacement) = translate_finder.replace_with_string(input) {
+        output = replacement;
+      }
+    } else {
+      break;
+    }
+  }
+
+  output
+}
+
 struct TranslateFinder {
   regex: Regex,
-  map: serde_json::Value
+  map: serde_json::Value,
+  missing: MissingMode,
+  fallback_maps: Vec<serde_json::Value>
 }
 
 impl TranslateFinder {
   pub fn new(map: serde_json::Value) -> Self {
-    TranslateFinder { 
-      regex: Regex::new(&r#"['"](?P<key>[^|]+)['"] ?\| ?translate"#).unwrap(),
-      map
+    Self::with_filter_name(map, "translate")
+  }
+
+  pub fn with_filter_name(map: serde_json::Value, filter_name: &str) -> Self {
+    TranslateFinder {
+      regex: build_translate_regex(filter_name),
+      map,
+      missing: MissingMode::Empty,
+      fallback_maps: Vec::new()
     }
   }
 
+  pub fn with_missing_mode(mut self, missing: MissingMode, fallback_maps: Vec<serde_json::Value>) -> Self {
+    self.missing = missing;
+    self.fallback_maps = fallback_maps;
+    self
+  }
+
   pub fn is_match(&self, sample: &str) -> bool {
     self.regex.is_match(sample)
   }
 
+  // Looks `key` up in the primary map, falling back to `fallback_maps` in
+  // order when in `MissingMode::Fallback` and the primary map has no string.
+  // Distinguishes "no value at this path" from "a value is there, but it's
+  // not a string" (e.g. a nested object) — the latter must never be blanked
+  // out, since that would silently destroy whatever the key pointed at.
+  fn resolve(&self, key: &str) -> Resolved {
+    let primary = read_json_path(&self.map, key);
+    if let Some(s) = primary.as_ref().and_then(|v| v.as_str()) {
+      return Resolved::Found(s.to_string());
+    }
+
+    if matches!(self.missing, MissingMode::Fallback) {
+      if let Some(s) = self.fallback_maps.iter()
+        .find_map(|map| read_json_path(map, key).and_then(|v| v.as_str().map(String::from))) {
+        return Resolved::Found(s);
+      }
+    }
+
+    if primary.is_some() {
+      Resolved::NotAString
+    } else {
+      Resolved::Absent
+    }
+  }
+
   pub fn replace_with_string(&self, sample: &str) -> Option<String> {
     let mut did_replace = false;
     let ret_string = String::from(self.regex.replace_all(sample, |caps: &Captures| {
-      format!("{}", 
-        read_json_path(&self.map, &caps[1]).map(|f: Value| { 
-          let s = f.as_str();
-          if s.is_some() {
+      let filters = parse_filter_chain(&caps["filters"]);
+      match self.resolve(&caps["key"]) {
+        Resolved::Found(s) => {
+          did_replace = true;
+          apply_filters(&s, &filters)
+        }
+        // A present-but-non-string value is never blanked out: `key` mode
+        // substitutes the bare key, every other mode leaves the match untouched.
+        Resolved::NotAString => match self.missing {
+          MissingMode::Key => {
             did_replace = true;
+            String::from(&caps["key"])
           }
-          return String::from(s.unwrap_or(&caps[0]));
-        }).unwrap_or(String::from(""))
-      )
+          MissingMode::Original => {
+            did_replace = true;
+            String::from(&caps[0])
+          }
+          MissingMode::Empty | MissingMode::Fallback => String::from(&caps[0])
+        },
+        Resolved::Absent => match self.missing {
+          MissingMode::Empty => String::from(""),
+          MissingMode::Key => {
+            did_replace = true;
+            String::from(&caps["key"])
+          }
+          MissingMode::Original => {
+            did_replace = true;
+            String::from(&caps[0])
+          }
+          MissingMode::Fallback => String::from("")
+        }
+      }
     }));
 
     if did_replace {
       return Some(ret_string);
-    } else { 
+    } else {
       return None;
     }
   }
@@ -111,45 +389,247 @@ impl TranslateFinder {
 
 }
 
+enum Resolved {
+  Found(String),
+  NotAString,
+  Absent
+}
+
+// Builds the key-matching regex for a given filter keyword (`translate` by
+// default), capturing any further `| filter` chain after it so post-processing
+// transforms can be parsed out and applied in order.
+fn build_translate_regex(filter_name: &str) -> Regex {
+  Regex::new(&format!(
+    r#"['"](?P<key>[^|]+)['"] ?\| ?{} ?(?P<filters>(?:\| ?\w+ ?)*)"#,
+    regex::escape(filter_name)
+  )).unwrap()
+}
+
+fn parse_filter_chain(raw: &str) -> Vec<String> {
+  raw.split('|')
+    .map(|filter| filter.trim().to_string())
+    .filter(|filter| !filter.is_empty())
+    .collect()
+}
+
+// Applies known transforms left-to-right; an unrecognized filter name is a no-op.
+fn apply_filters(value: &str, filters: &[String]) -> String {
+  filters.iter().fold(String::from(value), |acc, filter| {
+    match filter.as_str() {
+      "uppercase" => acc.to_uppercase(),
+      "lowercase" => acc.to_lowercase(),
+      "trim" => acc.trim().to_string(),
+      "capitalize" => capitalize(&acc),
+      _ => acc
+    }
+  })
+}
+
+fn capitalize(value: &str) -> String {
+  let mut chars = value.chars();
+  match chars.next() {
+    Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    None => String::new()
+  }
+}
+
+// Loads a translation file, dispatching on extension so JSON, YAML, TOML and
+// gettext sources can all be globbed together and fed through the same
+// dotted-path lookups once parsed into a serde_json::Value.
 fn get_json_value_from_fs_path(path: &Path) -> Option<serde_json::Value> {
   if !path.exists() {
     return None;
   }
 
-  let json = serde_json::from_str(&fs::read_to_string(path).unwrap());
-
-  json.ok()
+  match path.extension().and_then(|ext| ext.to_str()) {
+    Some("yaml") | Some("yml") => serde_yaml::from_str::<serde_yaml::Value>(&fs::read_to_string(path).unwrap())
+      .ok()
+      .and_then(|value| serde_json::to_value(value).ok()),
+    Some("toml") => toml::from_str::<toml::Value>(&fs::read_to_string(path).unwrap())
+      .ok()
+      .and_then(|value| serde_json::to_value(value).ok()),
+    Some("po") => Some(parse_po(&fs::read_to_string(path).unwrap())),
+    Some("mo") => fs::read(path).ok().map(|bytes| parse_mo(&bytes)),
+    _ => serde_json::from_str(&fs::read_to_string(path).unwrap()).ok()
+  }
 }
 
+// Parses a gettext PO file's msgid/msgstr pairs into a flat key -> string map.
+// Entries with an empty msgstr (untranslated) are left out of the map so they
+// fall through to the same missing-key handling as any other absent key.
+fn parse_po(contents: &str) -> Value {
+  let mut map = Map::new();
+  let mut msgid = String::new();
+  let mut msgstr = String::new();
+  let mut target: Option<&str> = None;
+
+  fn flush(map: &mut Map<String, Value>, msgid: &str, msgstr: &str) {
+    if !msgid.is_empty() && !msgstr.is_empty() {
+      map.insert(msgid.to_string(), Value::String(msgstr.to_string()));
+    }
+  }
+
+  for line in contents.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
 
+    if let Some(rest) = line.strip_prefix("msgid ") {
+      flush(&mut map, &msgid, &msgstr);
+      msgid = unescape_po_string(rest);
+      msgstr.clear();
+      target = Some("msgid");
+    } else if let Some(rest) = line.strip_prefix("msgstr ") {
+      msgstr = unescape_po_string(rest);
+      target = Some("msgstr");
+    } else if line.starts_with('"') {
+      match target {
+        Some("msgid") => msgid.push_str(&unescape_po_string(line)),
+        Some("msgstr") => msgstr.push_str(&unescape_po_string(line)),
+        _ => {}
+      }
+    }
+  }
+  flush(&mut map, &msgid, &msgstr);
 
+  Value::Object(map)
+}
 
-fn read_json_path(value: &Value, path: &str) -> Option<Value> {
-  path.split(".")
-  .enumerate()
-  .fold(None, |acc: Option<Value>, f| {
-    if f.0 == 0 {
-      if let Ok(i) = f.1.to_string().parse::<usize>() {
-        return Some(value[i].clone());
+fn unescape_po_string(raw: &str) -> String {
+  let trimmed = raw.trim();
+  let inner = trimmed.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(trimmed);
+
+  let mut result = String::new();
+  let mut chars = inner.chars();
+  while let Some(c) = chars.next() {
+    if c == '\\' {
+      match chars.next() {
+        Some('n') => result.push('\n'),
+        Some('t') => result.push('\t'),
+        Some('"') => result.push('"'),
+        Some('\\') => result.push('\\'),
+        Some(other) => result.push(other),
+        None => {}
       }
-      return Some(value[f.1].clone());
-    } else if acc.is_none() {
-      return None;
     } else {
-      if let Ok(i) = f.1.to_string().parse::<usize>() {
-        return Some(acc.unwrap()[i].clone());
+      result.push(c);
+    }
+  }
+  result
+}
+
+// Parses a compiled gettext MO file. Supports both byte orders; same
+// empty-msgstr-is-missing convention as `parse_po`.
+fn parse_mo(bytes: &[u8]) -> Value {
+  if bytes.len() < 20 {
+    return Value::Object(Map::new());
+  }
+
+  let little_endian = bytes[0..4] == [0xde, 0x12, 0x04, 0x95];
+  let big_endian = bytes[0..4] == [0x95, 0x04, 0x12, 0xde];
+  if !little_endian && !big_endian {
+    return Value::Object(Map::new());
+  }
+
+  // Any offset/length below is read from the file itself, so a truncated or
+  // corrupted table must not panic — bail out to an empty map, the same as
+  // the bad-magic-bytes case above, instead of indexing out of bounds.
+  let read_u32 = |offset: usize| -> Option<u32> {
+    let slice: [u8; 4] = bytes.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if little_endian { u32::from_le_bytes(slice) } else { u32::from_be_bytes(slice) })
+  };
+  let read_str = |offset: usize, len: usize| -> Option<String> {
+    Some(String::from_utf8_lossy(bytes.get(offset..offset + len)?).to_string())
+  };
+
+  let parse = || -> Option<Map<String, Value>> {
+    let nstrings = read_u32(8)? as usize;
+    let orig_table_offset = read_u32(12)? as usize;
+    let trans_table_offset = read_u32(16)? as usize;
+
+    let mut map = Map::new();
+
+    for i in 0..nstrings {
+      let orig_len = read_u32(orig_table_offset + i * 8)? as usize;
+      let orig_off = read_u32(orig_table_offset + i * 8 + 4)? as usize;
+      let trans_len = read_u32(trans_table_offset + i * 8)? as usize;
+      let trans_off = read_u32(trans_table_offset + i * 8 + 4)? as usize;
+
+      let msgid = read_str(orig_off, orig_len)?;
+      let msgstr = read_str(trans_off, trans_len)?;
+
+      if !msgid.is_empty() && !msgstr.is_empty() {
+        map.insert(msgid, Value::String(msgstr));
       }
-      return Some(acc.unwrap()[f.1].clone());
     }
-  })
+
+    Some(map)
+  };
+
+  Value::Object(parse().unwrap_or_default())
+}
+
+
+
+
+// Walks the same dot-separated path as `read_json_path`, creating nested
+// objects along the way, and sets the leaf to `leaf` only if it isn't set yet.
+// If an intermediate segment already holds a non-object value (e.g. an
+// existing translated string), the walk stops and leaves it untouched rather
+// than clobbering it with `{}`.
+fn set_json_path(value: &mut Value, path: &str, leaf: Value) {
+  let parts: Vec<&str> = path.split(".").collect();
+  let mut cursor = value;
+
+  for (i, part) in parts.iter().enumerate() {
+    match cursor {
+      Value::Object(_) => {}
+      Value::Null => *cursor = Value::Object(Map::new()),
+      _ => return
+    }
+
+    let map = cursor.as_object_mut().unwrap();
+    if i == parts.len() - 1 {
+      map.entry(part.to_string()).or_insert(leaf);
+      return;
+    }
+
+    cursor = map.entry(part.to_string()).or_insert(Value::Null);
+  }
+}
+
+// Unlike plain `Value` indexing (which returns `Value::Null` for an absent
+// key), this returns `None` when any segment of the path is actually absent,
+// so callers can tell "missing" apart from "present and null".
+fn read_json_path(value: &Value, path: &str) -> Option<Value> {
+  path.split(".")
+    .try_fold(value.clone(), |acc, segment| {
+      if let Ok(i) = segment.parse::<usize>() {
+        acc.get(i).cloned()
+      } else {
+        acc.get(segment).cloned()
+      }
+    })
 }
 
 #[cfg(test)]
 mod tests {
 
+use std::fs;
 use serde_json::{json};
 
-use super::TranslateFinder;
+use super::{TranslateFinder, MissingMode};
+
+// Each test that touches the filesystem gets its own directory under the OS
+// temp dir, named after the test and this process, so parallel test runs
+// don't collide.
+fn temp_dir_for(name: &str) -> std::path::PathBuf {
+  let dir = std::env::temp_dir().join(format!("translate_replace_test_{}_{}", name, std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
 
   #[test]
   fn translate_finder_finds_translates() {
@@ -177,6 +657,165 @@ use super::TranslateFinder;
     assert_eq!(replacement.unwrap(), String::from("                   hello, world"));
   }
 
+  #[test]
+  fn missing_key_alone_is_not_replaced_by_default() {
+
+    let translate_finder = TranslateFinder::new(json!({}));
+
+    let replacement = translate_finder.replace_with_string("{{ 'missing_key' | translate }}");
+    assert!(replacement.is_none());
+  }
+
+  #[test]
+  fn missing_mode_key_substitutes_bare_key() {
+
+    let translate_finder = TranslateFinder::new(json!({}))
+      .with_missing_mode(MissingMode::Key, Vec::new());
+
+    let replacement = translate_finder.replace_with_string("{{ 'missing_key' | translate }}");
+    assert_eq!(replacement.unwrap(), String::from("{{ missing_key}}"));
+  }
+
+  #[test]
+  fn missing_mode_original_leaves_match_untouched() {
+
+    let translate_finder = TranslateFinder::new(json!({}))
+      .with_missing_mode(MissingMode::Original, Vec::new());
+
+    let sample = "{{ 'missing_key' | translate }}";
+    let replacement = translate_finder.replace_with_string(sample);
+    assert_eq!(replacement.unwrap(), String::from(sample));
+  }
+
+  #[test]
+  fn missing_mode_fallback_consults_fallback_maps_in_order() {
+
+    let translate_finder = TranslateFinder::new(json!({}))
+      .with_missing_mode(MissingMode::Fallback, vec![
+        json!({}),
+        json!({ "my_custom_string": "hola" })
+      ]);
+
+    let replacement = translate_finder.replace_with_string("{{ 'my_custom_string' | translate }}");
+    assert_eq!(replacement.unwrap(), String::from("{{ hola}}"));
+  }
+
+  #[test]
+  fn non_string_values_are_left_untouched_even_when_other_keys_resolve() {
+
+    let translate_finder = TranslateFinder::new(json!({
+      "greeting": "hello",
+      "complex": {
+        "nested": "oops"
+      }
+    }));
+
+    let replacement = translate_finder.replace_with_string(
+      "{{ 'greeting' | translate }} {{ 'complex' | translate }}"
+    );
+
+    assert_eq!(
+      replacement.unwrap(),
+      String::from("{{ hello}} {{ 'complex' | translate }}")
+    );
+  }
+
+  #[test]
+  fn applies_chained_filters_after_lookup() {
+
+    let translate_finder = TranslateFinder::new(json!({
+      "greeting": "hello, world"
+    }));
+
+    let replacement = translate_finder.replace_with_string("{{ 'greeting' | translate | uppercase }}");
+    assert_eq!(replacement.unwrap(), String::from("{{ HELLO, WORLD}}"));
+  }
+
+  #[test]
+  fn custom_filter_name_is_matched_instead_of_translate() {
+
+    let translate_finder = TranslateFinder::with_filter_name(json!({
+      "greeting": "hello, world"
+    }), "i18n");
+
+    assert!(translate_finder.is_match("{{ 'greeting' | i18n }}"));
+    assert!(!translate_finder.is_match("{{ 'greeting' | translate }}"));
+  }
+
+  #[test]
+  fn loads_yaml_and_toml_translation_sources() {
+
+    let dir = temp_dir_for("formats");
+    let yaml_path = dir.join("translations.yaml");
+    let toml_path = dir.join("translations.toml");
+
+    fs::write(&yaml_path, "greeting: hello, world\n").unwrap();
+    fs::write(&toml_path, "greeting = \"hello, world\"\n").unwrap();
+
+    let yaml_value = super::get_json_value_from_fs_path(&yaml_path).unwrap();
+    assert_eq!(yaml_value["greeting"].as_str().unwrap(), "hello, world");
+
+    let toml_value = super::get_json_value_from_fs_path(&toml_path).unwrap();
+    assert_eq!(toml_value["greeting"].as_str().unwrap(), "hello, world");
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn parse_po_reads_msgid_msgstr_pairs() {
+
+    let po = "msgid \"greeting\"\nmsgstr \"hello, world\"\n\nmsgid \"untranslated\"\nmsgstr \"\"\n";
+
+    let value = super::parse_po(po);
+    assert_eq!(value["greeting"].as_str().unwrap(), "hello, world");
+    assert!(value["untranslated"].is_null());
+  }
+
+  #[test]
+  fn parse_mo_reads_msgid_msgstr_pairs() {
+
+    let msgid = b"greeting";
+    let msgstr = b"hello, world";
+
+    let header_size = 28;
+    let orig_table_offset = header_size;
+    let trans_table_offset = orig_table_offset + 8;
+    let strings_offset = trans_table_offset + 8;
+    let msgid_offset = strings_offset;
+    let msgstr_offset = msgid_offset + msgid.len();
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&[0xde, 0x12, 0x04, 0x95]);
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&(orig_table_offset as u32).to_le_bytes());
+    bytes.extend_from_slice(&(trans_table_offset as u32).to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&(msgid.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(msgid_offset as u32).to_le_bytes());
+    bytes.extend_from_slice(&(msgstr.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(msgstr_offset as u32).to_le_bytes());
+    bytes.extend_from_slice(msgid);
+    bytes.extend_from_slice(msgstr);
+
+    let value = super::parse_mo(&bytes);
+    assert_eq!(value["greeting"].as_str().unwrap(), "hello, world");
+  }
+
+  #[test]
+  fn parse_mo_returns_empty_map_for_out_of_range_table_offsets() {
+
+    let mut bytes = vec![0u8; 20];
+    bytes[0..4].copy_from_slice(&[0xde, 0x12, 0x04, 0x95]);
+    bytes[8..12].copy_from_slice(&1u32.to_le_bytes());
+    bytes[12..16].copy_from_slice(&1000u32.to_le_bytes());
+    bytes[16..20].copy_from_slice(&1000u32.to_le_bytes());
+
+    let value = super::parse_mo(&bytes);
+    assert_eq!(value, json!({}));
+  }
+
   #[test]
   fn can_read_value_by_json_path() {
 
@@ -198,4 +837,115 @@ use super::TranslateFinder;
     let u = super::read_json_path(&json_payload, json_path);
     assert_eq!(u.unwrap().as_str().unwrap(), "Embedded 0");
   }
+
+  #[test]
+  fn extract_translations_adds_missing_keys_and_preserves_existing() {
+
+    let dir = temp_dir_for("extract");
+    let translations_path = dir.join("translations.json");
+    let template_path = dir.join("template.html");
+
+    fs::write(&translations_path, r#"{"greeting": "existing translation"}"#).unwrap();
+    fs::write(&template_path, "{{ 'greeting' | translate }} {{ 'farewell' | translate }}").unwrap();
+
+    super::extract_translations(
+      translations_path.to_str().unwrap(),
+      "^$",
+      &format!("{}/*.html", dir.to_str().unwrap()),
+      false,
+      "translate"
+    );
+
+    let result: serde_json::Value = serde_json::from_str(&fs::read_to_string(&translations_path).unwrap()).unwrap();
+    assert_eq!(result["greeting"].as_str().unwrap(), "existing translation");
+    assert_eq!(result["farewell"].as_str().unwrap(), "farewell");
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn translate_document_replaces_matches_from_the_given_translations() {
+
+    let translations = vec![
+      TranslateFinder::new(json!({
+        "greeting": "hello, world"
+      }))
+    ];
+
+    let output = super::translate_document(&translations, "{{ 'greeting' | translate }}");
+
+    assert_eq!(output, "{{ hello, world}}");
+  }
+
+  #[test]
+  fn check_translations_flags_unresolved_keys() {
+
+    let dir = temp_dir_for("check");
+    let translations_path = dir.join("translations.json");
+    let template_path = dir.join("template.html");
+
+    fs::write(&translations_path, r#"{"greeting": "hello, world"}"#).unwrap();
+    fs::write(&template_path, "{{ 'greeting' | translate }} {{ 'farewell' | translate }}").unwrap();
+
+    let has_unresolved = super::check_translations(
+      translations_path.to_str().unwrap(),
+      "^$",
+      &format!("{}/*.html", dir.to_str().unwrap()),
+      "translate"
+    );
+
+    assert!(has_unresolved);
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn read_json_path_returns_none_for_an_absent_key() {
+
+    let json_payload = json!({});
+
+    assert!(super::read_json_path(&json_payload, "farewell").is_none());
+  }
+
+  #[test]
+  fn can_set_value_by_json_path_creating_nested_objects() {
+
+    let mut json_payload = json!({});
+
+    super::set_json_path(&mut json_payload, "greeting.hello", json!("hello"));
+
+    assert_eq!(
+      super::read_json_path(&json_payload, "greeting.hello").unwrap().as_str().unwrap(),
+      "hello"
+    );
+  }
+
+  #[test]
+  fn set_json_path_does_not_clobber_an_existing_scalar_when_nesting() {
+
+    let mut json_payload = json!({
+      "foo": "existing translation"
+    });
+
+    super::set_json_path(&mut json_payload, "foo.bar", json!("bar"));
+
+    assert_eq!(json_payload["foo"].as_str().unwrap(), "existing translation");
+  }
+
+  #[test]
+  fn set_json_path_does_not_overwrite_existing_values() {
+
+    let mut json_payload = json!({
+      "greeting": {
+        "hello": "bonjour"
+      }
+    });
+
+    super::set_json_path(&mut json_payload, "greeting.hello", json!("hello"));
+
+    assert_eq!(
+      super::read_json_path(&json_payload, "greeting.hello").unwrap().as_str().unwrap(),
+      "bonjour"
+    );
+  }
 }
\ No newline at end of file